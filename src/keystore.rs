@@ -0,0 +1,37 @@
+// Generates and holds ed25519 keypairs for each named participant in the simulation.
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand::rngs::OsRng;
+use std::collections::HashMap;
+
+pub struct Keystore {
+    keys: HashMap<String, SigningKey>,
+}
+
+impl Keystore {
+    pub fn new() -> Keystore {
+        Keystore {
+            keys: HashMap::new(),
+        }
+    }
+
+    // Generate a fresh keypair for `name`, or return the existing one if already registered
+    pub fn generate(&mut self, name: &str) -> VerifyingKey {
+        if let Some(existing) = self.keys.get(name) {
+            return existing.verifying_key();
+        }
+
+        let mut csprng = OsRng;
+        let signing_key = SigningKey::generate(&mut csprng);
+        let public_key = signing_key.verifying_key();
+        self.keys.insert(name.to_string(), signing_key);
+        public_key
+    }
+
+    pub fn signing_key(&self, name: &str) -> Option<&SigningKey> {
+        self.keys.get(name)
+    }
+
+    pub fn public_key(&self, name: &str) -> Option<VerifyingKey> {
+        self.keys.get(name).map(|key| key.verifying_key())
+    }
+}
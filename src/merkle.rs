@@ -0,0 +1,110 @@
+// Merkle tree helpers: build a root over a block's transaction hashes, and produce/verify
+// inclusion proofs for a single leaf without needing the rest of the block body.
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    let result = hasher.finalize();
+
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&result);
+    out
+}
+
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let result = hasher.finalize();
+
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&result);
+    out
+}
+
+// Combine leaves pairwise up to a single root, duplicating the last node at any odd level
+pub fn root(leaves: &[Hash]) -> Hash {
+    assert!(!leaves.is_empty(), "a block must commit to at least one transaction");
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(*level.last().unwrap());
+        }
+        level = level.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+    }
+    level[0]
+}
+
+// Sibling hashes needed to prove `leaves[index]`'s inclusion in `root(leaves)`
+pub fn proof(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    let mut level = leaves.to_vec();
+    let mut idx = index;
+    let mut siblings = Vec::new();
+
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(*level.last().unwrap());
+        }
+
+        let sibling_idx = if idx.is_multiple_of(2) { idx + 1 } else { idx - 1 };
+        siblings.push(level[sibling_idx]);
+
+        level = level.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+        idx /= 2;
+    }
+
+    siblings
+}
+
+// Recompute the root from a leaf and its sibling proof, without the rest of the tree
+pub fn verify(leaf: Hash, index: usize, proof: &[Hash], expected_root: Hash) -> bool {
+    let mut hash = leaf;
+    let mut idx = index;
+
+    for sibling in proof {
+        hash = if idx.is_multiple_of(2) {
+            hash_pair(&hash, sibling)
+        } else {
+            hash_pair(sibling, &hash)
+        };
+        idx /= 2;
+    }
+
+    hash == expected_root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_leaf_tree_roots_to_the_leaf_itself() {
+        let leaf = leaf_hash(b"only transaction");
+        assert_eq!(root(&[leaf]), leaf);
+    }
+
+    #[test]
+    fn proof_verifies_every_leaf_in_an_odd_sized_tree() {
+        let leaves: Vec<Hash> = (0..5u8).map(|i| leaf_hash(&[i])).collect();
+        let expected_root = root(&leaves);
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let inclusion_proof = proof(&leaves, index);
+            assert!(verify(*leaf, index, &inclusion_proof, expected_root));
+        }
+    }
+
+    #[test]
+    fn proof_rejects_a_leaf_that_was_never_in_the_tree() {
+        let leaves: Vec<Hash> = (0..4u8).map(|i| leaf_hash(&[i])).collect();
+        let expected_root = root(&leaves);
+        let inclusion_proof = proof(&leaves, 0);
+
+        let forged_leaf = leaf_hash(b"not in the tree");
+        assert!(!verify(forged_leaf, 0, &inclusion_proof, expected_root));
+    }
+}
@@ -0,0 +1,108 @@
+// Persists the chain to a SQLite database so a simulation can be resumed across runs.
+use crate::transaction;
+use crate::{Block, BlockHeader};
+use rusqlite::{params, Connection};
+
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    pub fn open(db_path: &str) -> rusqlite::Result<Storage> {
+        let conn = Connection::open(db_path)?;
+        // `data` holds the block's transactions via `transaction::encode`, not a literal
+        // transfer string — the request predates chunk0-4/chunk0-5 landing first, which
+        // replaced the single `data: String` with a signed, multi-transaction body.
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS blocks (
+                idx INTEGER PRIMARY KEY,
+                previous_hash TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                nonce INTEGER NOT NULL,
+                difficulty INTEGER NOT NULL,
+                merkle_root BLOB NOT NULL,
+                hash TEXT NOT NULL,
+                data BLOB NOT NULL
+            )",
+        )?;
+        Ok(Storage { conn })
+    }
+
+    // Load every persisted block, in index order
+    pub fn load_chain(&self) -> rusqlite::Result<Vec<Block>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT idx, previous_hash, timestamp, nonce, difficulty, merkle_root, hash, data
+             FROM blocks ORDER BY idx",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let merkle_root: Vec<u8> = row.get(5)?;
+            let mut root = [0u8; 32];
+            root.copy_from_slice(&merkle_root);
+
+            let data: Vec<u8> = row.get(7)?;
+            let transactions = transaction::decode(&data)
+                .unwrap_or_else(|err| panic!("corrupt transaction data in blocks table: {}", err));
+
+            Ok(Block {
+                header: BlockHeader {
+                    index: row.get(0)?,
+                    previous_hash: row.get(1)?,
+                    timestamp: row.get::<_, i64>(2)? as u64,
+                    nonce: row.get::<_, i64>(3)? as u64,
+                    merkle_root: root,
+                },
+                difficulty: row.get::<_, i64>(4)? as usize,
+                hash: row.get(6)?,
+                transactions,
+            })
+        })?;
+
+        rows.collect()
+    }
+
+    // Insert one newly mined block, wrapped in its own SQLite transaction
+    pub fn insert_block(&mut self, block: &Block) -> rusqlite::Result<()> {
+        let data = transaction::encode(&block.transactions);
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO blocks (idx, previous_hash, timestamp, nonce, difficulty, merkle_root, hash, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                block.header.index,
+                block.header.previous_hash,
+                block.header.timestamp as i64,
+                block.header.nonce as i64,
+                block.difficulty as i64,
+                block.header.merkle_root.to_vec(),
+                block.hash,
+                data,
+            ],
+        )?;
+        tx.commit()
+    }
+
+    // Replace the whole table with `chain`, used to checkpoint after direct in-memory changes
+    pub fn save_chain(&mut self, chain: &[Block]) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM blocks", [])?;
+        for block in chain {
+            let data = transaction::encode(&block.transactions);
+            tx.execute(
+                "INSERT INTO blocks (idx, previous_hash, timestamp, nonce, difficulty, merkle_root, hash, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    block.header.index,
+                    block.header.previous_hash,
+                    block.header.timestamp as i64,
+                    block.header.nonce as i64,
+                    block.difficulty as i64,
+                    block.header.merkle_root.to_vec(),
+                    block.hash,
+                    data,
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+}
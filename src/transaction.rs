@@ -0,0 +1,233 @@
+// A signed transfer of NovaCoin from a keyholder to a named recipient.
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+
+pub struct Transaction {
+    pub sender_pubkey: VerifyingKey,
+    pub recipient: String,
+    pub amount: u64,
+    pub signature: Signature,
+}
+
+impl Transaction {
+    fn message(sender_pubkey: &VerifyingKey, recipient: &str, amount: u64) -> Vec<u8> {
+        let mut message = sender_pubkey.to_bytes().to_vec();
+        message.extend_from_slice(recipient.as_bytes());
+        message.extend_from_slice(&amount.to_be_bytes());
+        message
+    }
+
+    // Build and sign a transaction with the sender's private key
+    pub fn new_signed(sender_key: &SigningKey, recipient: String, amount: u64) -> Transaction {
+        let sender_pubkey = sender_key.verifying_key();
+        let signature = sender_key.sign(&Self::message(&sender_pubkey, &recipient, amount));
+        Transaction {
+            sender_pubkey,
+            recipient,
+            amount,
+            signature,
+        }
+    }
+
+    // Confirm the signature was produced by `sender_pubkey` over this transaction's contents
+    pub fn verify_signature(&self) -> bool {
+        let message = Self::message(&self.sender_pubkey, &self.recipient, self.amount);
+        self.sender_pubkey.verify(&message, &self.signature).is_ok()
+    }
+
+    // Deterministic byte encoding folded into the enclosing block's hash
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Self::message(&self.sender_pubkey, &self.recipient, self.amount);
+        bytes.extend_from_slice(&self.signature.to_bytes());
+        bytes
+    }
+}
+
+// A transaction blob read back from storage didn't match the encoding `encode` writes
+#[derive(Debug)]
+pub struct DecodeError;
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "malformed transaction data")
+    }
+}
+
+impl Error for DecodeError {}
+
+// Fixed-width binary encoding used to persist a block's transactions (sender_pubkey: 32 bytes,
+// recipient: u32 length-prefixed UTF-8, amount: 8 bytes, signature: 64 bytes), one after another.
+pub fn encode(transactions: &[Transaction]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(transactions.len() as u32).to_be_bytes());
+
+    for tx in transactions {
+        bytes.extend_from_slice(&tx.sender_pubkey.to_bytes());
+        bytes.extend_from_slice(&(tx.recipient.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(tx.recipient.as_bytes());
+        bytes.extend_from_slice(&tx.amount.to_be_bytes());
+        bytes.extend_from_slice(&tx.signature.to_bytes());
+    }
+
+    bytes
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Vec<Transaction>, DecodeError> {
+    let mut cursor = bytes;
+    let count = take_u32(&mut cursor).ok_or(DecodeError)?;
+
+    let mut transactions = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let sender_pubkey_bytes = take(&mut cursor, 32).ok_or(DecodeError)?;
+        let sender_pubkey = VerifyingKey::from_bytes(sender_pubkey_bytes.try_into().unwrap())
+            .map_err(|_| DecodeError)?;
+
+        let recipient_len = take_u32(&mut cursor).ok_or(DecodeError)? as usize;
+        let recipient_bytes = take(&mut cursor, recipient_len).ok_or(DecodeError)?;
+        let recipient = String::from_utf8(recipient_bytes.to_vec()).map_err(|_| DecodeError)?;
+
+        let amount_bytes = take(&mut cursor, 8).ok_or(DecodeError)?;
+        let amount = u64::from_be_bytes(amount_bytes.try_into().unwrap());
+
+        let signature_bytes = take(&mut cursor, 64).ok_or(DecodeError)?;
+        let signature = Signature::from_bytes(signature_bytes.try_into().unwrap());
+
+        transactions.push(Transaction {
+            sender_pubkey,
+            recipient,
+            amount,
+            signature,
+        });
+    }
+
+    Ok(transactions)
+}
+
+fn take<'a>(cursor: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if cursor.len() < len {
+        return None;
+    }
+    let (head, tail) = cursor.split_at(len);
+    *cursor = tail;
+    Some(head)
+}
+
+fn take_u32(cursor: &mut &[u8]) -> Option<u32> {
+    take(cursor, 4).map(|bytes| u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, DecodeError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(DecodeError);
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| DecodeError))
+        .collect()
+}
+
+// JSON shape for a transaction: the same fields as `Transaction`, but with the pubkey and
+// signature hex-encoded rather than serialized as raw byte arrays.
+#[derive(Serialize, Deserialize)]
+struct TransactionJson {
+    sender_pubkey: String,
+    recipient: String,
+    amount: u64,
+    signature: String,
+}
+
+impl Serialize for Transaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TransactionJson {
+            sender_pubkey: to_hex(&self.sender_pubkey.to_bytes()),
+            recipient: self.recipient.clone(),
+            amount: self.amount,
+            signature: to_hex(&self.signature.to_bytes()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Transaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = TransactionJson::deserialize(deserializer)?;
+
+        let pubkey_bytes = from_hex(&raw.sender_pubkey).map_err(DeError::custom)?;
+        let pubkey_bytes: [u8; 32] = pubkey_bytes
+            .try_into()
+            .map_err(|_| DeError::custom("sender_pubkey must be 32 bytes"))?;
+        let sender_pubkey = VerifyingKey::from_bytes(&pubkey_bytes).map_err(DeError::custom)?;
+
+        let signature_bytes = from_hex(&raw.signature).map_err(DeError::custom)?;
+        let signature_bytes: [u8; 64] = signature_bytes
+            .try_into()
+            .map_err(|_| DeError::custom("signature must be 64 bytes"))?;
+        let signature = Signature::from_bytes(&signature_bytes);
+
+        Ok(Transaction {
+            sender_pubkey,
+            recipient: raw.recipient,
+            amount: raw.amount,
+            signature,
+        })
+    }
+}
+
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sender_prefix: String = self
+            .sender_pubkey
+            .to_bytes()
+            .iter()
+            .take(4)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        write!(
+            f,
+            "{} NovaCoin from {}.. to {}",
+            self.amount, sender_prefix, self.recipient
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn a_freshly_signed_transaction_verifies() {
+        let sender_key = SigningKey::generate(&mut OsRng);
+        let tx = Transaction::new_signed(&sender_key, "recipient".to_string(), 42);
+        assert!(tx.verify_signature());
+    }
+
+    #[test]
+    fn tampering_with_the_amount_breaks_the_signature() {
+        let sender_key = SigningKey::generate(&mut OsRng);
+        let mut tx = Transaction::new_signed(&sender_key, "recipient".to_string(), 42);
+        tx.amount += 1;
+        assert!(!tx.verify_signature());
+    }
+
+    #[test]
+    fn json_round_trip_preserves_the_transaction() {
+        let sender_key = SigningKey::generate(&mut OsRng);
+        let tx = Transaction::new_signed(&sender_key, "recipient".to_string(), 42);
+
+        let json = serde_json::to_string(&tx).expect("serialize");
+        let decoded: Transaction = serde_json::from_str(&json).expect("deserialize");
+
+        assert_eq!(decoded.sender_pubkey, tx.sender_pubkey);
+        assert_eq!(decoded.recipient, tx.recipient);
+        assert_eq!(decoded.amount, tx.amount);
+        assert!(decoded.verify_signature());
+    }
+}